@@ -0,0 +1,49 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use plonky2::gadgets::arithmetic_u32::U32Target;
+use plonky2::gates::blake3::Blake3Gate;
+use plonky2::hash::blake3::Blake3GoldilocksConfig;
+use plonky2::iop::witness::PartialWitness;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::plonk::circuit_data::CircuitConfig;
+use plonky2::plonk::config::PoseidonGoldilocksConfig;
+
+/// Benchmarks building and proving a circuit that performs a single BLAKE3 compression via
+/// `CircuitBuilder::blake3`.
+fn bench_blake3_prove(c: &mut Criterion) {
+    const D: usize = 2;
+    type F = <PoseidonGoldilocksConfig as plonky2::plonk::config::GenericConfig<D>>::F;
+
+    c.bench_function("blake3_prove", |b| {
+        b.iter(|| {
+            // `Blake3Gate` needs far more wire columns per row than the standard config budgets
+            // for; see `Blake3Gate::min_wires_config`.
+            let config = CircuitConfig {
+                num_wires: Blake3Gate::<F, D>::min_wires_config(),
+                ..CircuitConfig::standard_recursion_config()
+            };
+            let mut builder = CircuitBuilder::<F, D>::new(config);
+
+            let cv: [U32Target; 8] =
+                std::array::from_fn(|_| builder.add_virtual_u32_target());
+            let message: [U32Target; 16] =
+                std::array::from_fn(|_| builder.add_virtual_u32_target());
+
+            let digest = builder.blake3(cv, message, 0, 64, 0);
+            for word in digest {
+                builder.register_public_input(word.0);
+            }
+
+            let mut pw = PartialWitness::new();
+            for target in cv.iter().chain(message.iter()) {
+                pw.set_target(target.0, F::ZERO);
+            }
+
+            let data = builder.build::<Blake3GoldilocksConfig>();
+            let proof = data.prove(pw).unwrap();
+            data.verify(proof).unwrap();
+        })
+    });
+}
+
+criterion_group!(benches, bench_blake3_prove);
+criterion_main!(benches);
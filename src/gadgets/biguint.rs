@@ -1,9 +1,10 @@
 use std::marker::PhantomData;
 
-use num::Integer;
+use num::bigint::{BigInt, ToBigInt};
+use num::{BigUint, Integer, One, Zero};
 
 use crate::field::extension_field::Extendable;
-use crate::field::field_types::RichField;
+use crate::field::field_types::{Field, RichField};
 use crate::gadgets::arithmetic_u32::U32Target;
 use crate::iop::generator::{GeneratedValues, SimpleGenerator};
 use crate::iop::target::{BoolTarget, Target};
@@ -71,6 +72,13 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         self.list_le(a_vec, b_vec, 32)
     }
 
+    /// Whether `a` is exactly zero, i.e. `a <= 0` (every `BigUintTarget` value is nonnegative, so
+    /// that is equivalent to equality).
+    fn is_zero_biguint(&mut self, a: BigUintTarget) -> BoolTarget {
+        let zero = self.constant_biguint(&BigUint::zero());
+        self.cmp_biguint(a, zero)
+    }
+
     fn add_virtual_biguint_target(&mut self, num_limbs: usize) -> BigUintTarget {
         let limbs = (0..num_limbs)
             .map(|_| self.add_virtual_u32_target())
@@ -79,6 +87,17 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         BigUintTarget { limbs }
     }
 
+    /// A `BigUintTarget` whose limbs are wired to the constant `value`.
+    pub fn constant_biguint(&mut self, value: &BigUint) -> BigUintTarget {
+        let limbs = value
+            .to_u32_digits()
+            .iter()
+            .map(|&limb| U32Target(self.constant(F::from_canonical_u32(limb))))
+            .collect();
+
+        BigUintTarget { limbs }
+    }
+
     // Add two `BigUintTarget`s.
     pub fn add_biguint(&mut self, a: BigUintTarget, b: BigUintTarget) -> BigUintTarget {
         let num_limbs = a.limbs.len();
@@ -173,6 +192,310 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
 
         (div, rem)
     }
+
+    /// `a` reduced modulo `n`, i.e. the remainder of `div_rem_biguint(a, n)`.
+    pub fn rem_biguint(&mut self, a: BigUintTarget, n: BigUintTarget) -> BigUintTarget {
+        let (_, rem) = self.div_rem_biguint(a, n);
+        rem
+    }
+
+    /// `a * b mod n`.
+    pub fn mul_mod_biguint(
+        &mut self,
+        a: BigUintTarget,
+        b: BigUintTarget,
+        n: BigUintTarget,
+    ) -> BigUintTarget {
+        let num_limbs = n.num_limbs();
+        let product = self.mul_biguint(a, b);
+        // `div_rem_biguint` sizes its remainder to the dividend's (here, the product's) limb count,
+        // which is wider than `n`. The remainder is `< n` by construction, so its high limbs above
+        // `n`'s width are provably zero; truncate back down to `n`'s width so callers that expect
+        // `a * b mod n` to have the same width as `n` (e.g. `pow_mod_biguint`'s `select_biguint`)
+        // don't see a limb-count mismatch.
+        let remainder = self.rem_biguint(product, n);
+        self.truncate_biguint(remainder, num_limbs)
+    }
+
+    /// The modular inverse of `a` modulo `n`, i.e. the unique `a_inv < n` with
+    /// `a * a_inv == 1 (mod n)`. A generator nondeterministically supplies `a_inv` (computed out of
+    /// circuit via the extended Euclidean algorithm); the circuit then checks both that it is the
+    /// correct inverse and that it is fully reduced.
+    pub fn inv_mod_biguint(&mut self, a: BigUintTarget, n: BigUintTarget) -> BigUintTarget {
+        let num_limbs = n.limbs.len();
+        let inv = self.add_virtual_biguint_target(num_limbs);
+
+        self.add_simple_generator(BigUintInvModGenerator::<F, D> {
+            a: a.clone(),
+            n: n.clone(),
+            inv: inv.clone(),
+            _phantom: PhantomData,
+        });
+
+        let one = self.constant_biguint(&BigUint::one());
+        let one = self.pad_biguint_to(one, num_limbs);
+        let product_mod_n = self.mul_mod_biguint(a, inv.clone(), n.clone());
+        self.connect_biguint(product_mod_n, one);
+
+        let inv_lt_n = self.cmp_biguint(inv.clone(), n);
+        self.assert_one(inv_lt_n.target);
+
+        inv
+    }
+
+    /// `base^exp mod n`, via square-and-multiply over `exp_bits` (little-endian).
+    pub fn pow_mod_biguint(
+        &mut self,
+        base: BigUintTarget,
+        exp_bits: &[BoolTarget],
+        n: BigUintTarget,
+    ) -> BigUintTarget {
+        let num_limbs = n.limbs.len();
+        let mut result = self.constant_biguint(&BigUint::one());
+        result = self.pad_biguint_to(result, num_limbs);
+        let mut squared = self.pad_biguint_to(base, num_limbs);
+
+        for &bit in exp_bits {
+            let multiplied = self.mul_mod_biguint(result.clone(), squared.clone(), n.clone());
+            result = self.select_biguint(bit, multiplied, result);
+            squared = self.mul_mod_biguint(squared.clone(), squared.clone(), n.clone());
+        }
+
+        result
+    }
+
+    /// Select between two `BigUintTarget`s of equal length based on a boolean condition, limb by
+    /// limb (the `_if`-style conditional selection used to implement square-and-multiply).
+    fn select_biguint(
+        &mut self,
+        cond: BoolTarget,
+        a: BigUintTarget,
+        b: BigUintTarget,
+    ) -> BigUintTarget {
+        debug_assert_eq!(a.num_limbs(), b.num_limbs());
+        let limbs = a
+            .limbs
+            .iter()
+            .zip(b.limbs.iter())
+            .map(|(&a_limb, &b_limb)| U32Target(self.select(cond, a_limb.0, b_limb.0)))
+            .collect();
+        BigUintTarget { limbs }
+    }
+
+    /// Zero-extend `a` to `num_limbs` limbs (assumes `a` has at most that many limbs already).
+    fn pad_biguint_to(&mut self, a: BigUintTarget, num_limbs: usize) -> BigUintTarget {
+        debug_assert!(a.num_limbs() <= num_limbs);
+        let zero = self.zero_u32();
+        let mut limbs = a.limbs;
+        limbs.resize(num_limbs, zero);
+        BigUintTarget { limbs }
+    }
+
+    /// Drop `a`'s limbs above index `num_limbs`, asserting that they are zero. The inverse of
+    /// [`Self::pad_biguint_to`]; used to bring a value that is provably `< 2^(32*num_limbs)` back
+    /// down to that width after a computation (such as `div_rem_biguint`) that returns it with more
+    /// limbs than necessary.
+    fn truncate_biguint(&mut self, a: BigUintTarget, num_limbs: usize) -> BigUintTarget {
+        debug_assert!(a.num_limbs() >= num_limbs);
+        for i in num_limbs..a.num_limbs() {
+            self.assert_zero_u32(a.get_limb(i));
+        }
+        BigUintTarget {
+            limbs: a.limbs[..num_limbs].to_vec(),
+        }
+    }
+
+    /// `gcd(a, b)`, verified via a nondeterministic witness rather than by running the (not
+    /// constraint-friendly) Euclidean algorithm in-circuit. A generator computes `g = gcd(a, b)`,
+    /// the exact quotients `qa = a / g`, `qb = b / g`, and signed Bezout coefficients `s, t` with
+    /// `s*a + t*b == g`, all out of circuit. The circuit then checks the quotients make `g` a common
+    /// divisor (`g*qa == a`, `g*qb == b`) and checks the Bezout identity to pin `g` down as the
+    /// *greatest* common divisor: since `s`/`t` may be negative, each is witnessed as a sign bit plus
+    /// a `BigUintTarget` magnitude, and the identity is asserted as an equality of two purely
+    /// nonnegative sums, `positive_side == g + negative_side`.
+    pub fn gcd_biguint(&mut self, a: BigUintTarget, b: BigUintTarget) -> BigUintTarget {
+        let num_limbs = a.num_limbs().max(b.num_limbs());
+        let a = self.pad_biguint_to(a, num_limbs);
+        let b = self.pad_biguint_to(b, num_limbs);
+
+        let g = self.add_virtual_biguint_target(num_limbs);
+        let qa = self.add_virtual_biguint_target(num_limbs);
+        let qb = self.add_virtual_biguint_target(num_limbs);
+        let s_mag = self.add_virtual_biguint_target(num_limbs);
+        let t_mag = self.add_virtual_biguint_target(num_limbs);
+        // `s_neg`/`t_neg` select which side of the Bezout identity each term lands on, so they must
+        // actually be boolean: `_unsafe` would let a prover pick an arbitrary field element here and
+        // use `select_biguint`'s unchecked linear interpolation to forge a witness for a non-gcd `g`.
+        let s_neg = self.add_virtual_bool_target_safe();
+        let t_neg = self.add_virtual_bool_target_safe();
+
+        self.add_simple_generator(BigUintGcdGenerator::<F, D> {
+            a: a.clone(),
+            b: b.clone(),
+            g: g.clone(),
+            qa: qa.clone(),
+            qb: qb.clone(),
+            s_mag: s_mag.clone(),
+            t_mag: t_mag.clone(),
+            s_neg,
+            t_neg,
+            _phantom: PhantomData,
+        });
+
+        // `g` is a common divisor: g*qa == a and g*qb == b exactly.
+        let g_qa = self.mul_biguint(g.clone(), qa);
+        let a_padded = self.pad_biguint_to(a.clone(), g_qa.num_limbs());
+        self.connect_biguint(g_qa, a_padded);
+
+        let g_qb = self.mul_biguint(g.clone(), qb);
+        let b_padded = self.pad_biguint_to(b.clone(), g_qb.num_limbs());
+        self.connect_biguint(g_qb, b_padded);
+
+        // `g` is the *greatest* common divisor: s*a + t*b == g for the witnessed Bezout coefficients.
+        let s_a = self.mul_biguint(s_mag, a);
+        let t_b = self.mul_biguint(t_mag, b);
+        let wide_limbs = s_a.num_limbs().max(t_b.num_limbs());
+        let s_a = self.pad_biguint_to(s_a, wide_limbs);
+        let t_b = self.pad_biguint_to(t_b, wide_limbs);
+        let zero = self.constant_biguint(&BigUint::zero());
+        let zero = self.pad_biguint_to(zero, wide_limbs);
+
+        let s_a_if_pos = self.select_biguint(s_neg, zero.clone(), s_a.clone());
+        let t_b_if_pos = self.select_biguint(t_neg, zero.clone(), t_b.clone());
+        let positive_side = self.add_biguint(s_a_if_pos, t_b_if_pos);
+
+        let s_a_if_neg = self.select_biguint(s_neg, s_a, zero.clone());
+        let t_b_if_neg = self.select_biguint(t_neg, t_b, zero);
+        let negative_side = self.add_biguint(s_a_if_neg, t_b_if_neg);
+
+        let g_padded = self.pad_biguint_to(g.clone(), negative_side.num_limbs());
+        let g_plus_negative_side = self.add_biguint(g_padded, negative_side);
+        self.connect_biguint(positive_side, g_plus_negative_side);
+
+        g
+    }
+
+    /// `lcm(a, b) = (a * b) / gcd(a, b)`, taking the (exact) quotient of `div_rem_biguint`.
+    ///
+    /// `gcd(a, b)` is zero exactly when `a == b == 0`, in which case `product` is zero too and the
+    /// lcm is defined to be zero. `div_rem_biguint` can't divide by zero, so that case is handled by
+    /// substituting a safe nonzero divisor and then overriding the result back to zero.
+    pub fn lcm_biguint(&mut self, a: BigUintTarget, b: BigUintTarget) -> BigUintTarget {
+        let gcd = self.gcd_biguint(a.clone(), b.clone());
+        let product = self.mul_biguint(a, b);
+        let gcd = self.pad_biguint_to(gcd, product.num_limbs());
+
+        let gcd_is_zero = self.is_zero_biguint(gcd.clone());
+        let one = self.constant_biguint(&BigUint::one());
+        let one = self.pad_biguint_to(one, gcd.num_limbs());
+        let safe_divisor = self.select_biguint(gcd_is_zero, one, gcd);
+
+        let (quotient, _remainder) = self.div_rem_biguint(product, safe_divisor);
+        let zero = self.constant_biguint(&BigUint::zero());
+        let zero = self.pad_biguint_to(zero, quotient.num_limbs());
+        self.select_biguint(gcd_is_zero, zero, quotient)
+    }
+}
+
+#[derive(Debug)]
+struct BigUintInvModGenerator<F: RichField + Extendable<D>, const D: usize> {
+    a: BigUintTarget,
+    n: BigUintTarget,
+    inv: BigUintTarget,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F>
+    for BigUintInvModGenerator<F, D>
+{
+    fn dependencies(&self) -> Vec<Target> {
+        self.a
+            .limbs
+            .iter()
+            .map(|&l| l.0)
+            .chain(self.n.limbs.iter().map(|&l| l.0))
+            .collect()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let a = witness.get_biguint_target(self.a.clone());
+        let n = witness.get_biguint_target(self.n.clone());
+
+        // Extended Euclidean algorithm, computed out of circuit.
+        let (mut old_r, mut r) = (a.to_bigint().unwrap(), n.to_bigint().unwrap());
+        let (mut old_s, mut s) = (BigInt::one(), BigInt::zero());
+        while !r.is_zero() {
+            let quotient = &old_r / &r;
+            let new_r = &old_r - &quotient * &r;
+            old_r = std::mem::replace(&mut r, new_r);
+            let new_s = &old_s - &quotient * &s;
+            old_s = std::mem::replace(&mut s, new_s);
+        }
+
+        let n_bigint = n.to_bigint().unwrap();
+        let inv = ((old_s % &n_bigint) + &n_bigint) % &n_bigint;
+        let inv = inv.to_biguint().unwrap();
+
+        out_buffer.set_biguint_target(self.inv.clone(), inv);
+    }
+}
+
+#[derive(Debug)]
+struct BigUintGcdGenerator<F: RichField + Extendable<D>, const D: usize> {
+    a: BigUintTarget,
+    b: BigUintTarget,
+    g: BigUintTarget,
+    qa: BigUintTarget,
+    qb: BigUintTarget,
+    s_mag: BigUintTarget,
+    t_mag: BigUintTarget,
+    s_neg: BoolTarget,
+    t_neg: BoolTarget,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F> for BigUintGcdGenerator<F, D> {
+    fn dependencies(&self) -> Vec<Target> {
+        self.a
+            .limbs
+            .iter()
+            .map(|&l| l.0)
+            .chain(self.b.limbs.iter().map(|&l| l.0))
+            .collect()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let a = witness.get_biguint_target(self.a.clone());
+        let b = witness.get_biguint_target(self.b.clone());
+        let a_bigint = a.to_bigint().unwrap();
+        let b_bigint = b.to_bigint().unwrap();
+
+        // Extended Euclidean algorithm: finds gcd(a, b) along with Bezout coefficients s, t such
+        // that s*a + t*b == gcd(a, b).
+        let ext = a_bigint.extended_gcd(&b_bigint);
+        let g = ext.gcd.to_biguint().unwrap();
+
+        // `gcd(0, 0) == 0`, so the quotients `a / g`, `b / g` would divide by zero; both `a` and
+        // `b` are zero in that case, so the (otherwise-undefined) quotients are harmlessly zero.
+        let (qa, qb) = if g.is_zero() {
+            (BigUint::zero(), BigUint::zero())
+        } else {
+            (&a / &g, &b / &g)
+        };
+
+        let s_neg = ext.x.sign() == num::bigint::Sign::Minus;
+        let t_neg = ext.y.sign() == num::bigint::Sign::Minus;
+        let s_mag = (-&ext.x).to_biguint().unwrap_or_else(|| ext.x.to_biguint().unwrap());
+        let t_mag = (-&ext.y).to_biguint().unwrap_or_else(|| ext.y.to_biguint().unwrap());
+
+        out_buffer.set_biguint_target(self.g.clone(), g);
+        out_buffer.set_biguint_target(self.qa.clone(), qa);
+        out_buffer.set_biguint_target(self.qb.clone(), qb);
+        out_buffer.set_biguint_target(self.s_mag.clone(), s_mag);
+        out_buffer.set_biguint_target(self.t_mag.clone(), t_mag);
+        out_buffer.set_bool_target(self.s_neg, s_neg);
+        out_buffer.set_bool_target(self.t_neg, t_neg);
+    }
 }
 
 #[derive(Debug)]
@@ -208,6 +531,46 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F>
 
 #[cfg(test)]
 mod tests {
+    use num::BigUint;
+
+    use super::*;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
     #[test]
     fn test_biguint_add() {}
+
+    #[test]
+    fn mul_mod_biguint_matches_modulus_width() {
+        const D: usize = 2;
+        type F = <PoseidonGoldilocksConfig as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let a = builder.constant_biguint(&BigUint::from(123456789u64));
+        let b = builder.constant_biguint(&BigUint::from(987654321u64));
+        let n = builder.constant_biguint(&BigUint::from(1000000007u64));
+
+        let result = builder.mul_mod_biguint(a, b, n.clone());
+        assert_eq!(result.num_limbs(), n.num_limbs());
+    }
+
+    #[test]
+    fn gcd_and_lcm_of_zero_do_not_divide_by_zero() {
+        const D: usize = 2;
+        type F = <PoseidonGoldilocksConfig as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let a = builder.constant_biguint(&BigUint::zero());
+        let b = builder.constant_biguint(&BigUint::zero());
+
+        // Neither of these should panic (dividing a quotient by a zero gcd).
+        let gcd = builder.gcd_biguint(a.clone(), b.clone());
+        let lcm = builder.lcm_biguint(a, b);
+        assert_eq!(gcd.num_limbs(), 1);
+        assert_eq!(lcm.num_limbs(), 1);
+    }
 }
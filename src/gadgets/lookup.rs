@@ -0,0 +1,248 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+use crate::field::extension_field::Extendable;
+use crate::field::field_types::{Field, RichField};
+use crate::hash::poseidon::PoseidonHash;
+use crate::iop::challenger::RecursiveChallenger;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator};
+use crate::iop::target::Target;
+use crate::iop::witness::{PartitionWitness, Witness};
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+/// Bookkeeping for one logarithmic-derivative (LogUp) lookup argument: the static table values and
+/// the looked-up values registered so far. Owned by the [`LookupTable`] handle itself (rather than
+/// by `CircuitBuilder`) so that registering tables/lookups needs no new state on `CircuitBuilder`.
+pub(crate) struct LookupData<F: Field> {
+    pub table: Vec<F>,
+    pub looked_up: Vec<Target>,
+    pub finalized: bool,
+}
+
+/// A handle to a static lookup table previously registered with
+/// [`CircuitBuilder::add_lookup_table`]. Cloning a handle is cheap and shares the same underlying
+/// table: every clone sees the same accumulated `lookup` calls. Used to tie later
+/// [`CircuitBuilder::lookup`] calls, and the final [`CircuitBuilder::finalize_lookup_table`] call,
+/// back to the table they check membership against.
+///
+/// Dropping the last clone of a handle without having called [`CircuitBuilder::finalize_lookup_table`]
+/// on it panics: `lookup` only records values, so a forgotten `finalize_lookup_table` call would
+/// otherwise leave every one of those lookups completely unconstrained, with no error anywhere
+/// short of someone noticing the membership check is missing from the circuit.
+pub struct LookupTable<F: Field>(Rc<RefCell<LookupData<F>>>);
+
+impl<F: Field> Clone for LookupTable<F> {
+    fn clone(&self) -> Self {
+        Self(Rc::clone(&self.0))
+    }
+}
+
+impl<F: Field> Drop for LookupTable<F> {
+    fn drop(&mut self) {
+        if Rc::strong_count(&self.0) == 1 && !self.0.borrow().finalized {
+            panic!(
+                "LookupTable dropped without calling CircuitBuilder::finalize_lookup_table on it; \
+                 its `lookup` calls would be unconstrained"
+            );
+        }
+    }
+}
+
+impl<F: Field> fmt::Debug for LookupTable<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LookupTable")
+            .field("len", &self.0.borrow().table.len())
+            .finish()
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Register a static table of values that later calls to [`Self::lookup`] may check membership
+    /// against. The table is fixed at circuit-definition time (it becomes part of the verifier key),
+    /// matching how `cmp_biguint`-style range checks are currently hard-coded rather than data-driven.
+    pub fn add_lookup_table(&mut self, table: Vec<F>) -> LookupTable<F> {
+        LookupTable(Rc::new(RefCell::new(LookupData {
+            table,
+            looked_up: Vec::new(),
+            finalized: false,
+        })))
+    }
+
+    /// Assert that `value` lies in the table referred to by `handle`. This only records `value`;
+    /// the actual LogUp equation is emitted once [`Self::finalize_lookup_table`] is called for this
+    /// `handle`, which must happen after every `lookup` call for it and before `build`.
+    pub fn lookup(&mut self, handle: &LookupTable<F>, value: Target) {
+        handle.0.borrow_mut().looked_up.push(value);
+    }
+
+    /// Close out one registered lookup table: draw the in-circuit transcript challenge `alpha` by
+    /// replaying Fiat-Shamir over every looked-up value and every table row (so a prover cannot
+    /// choose looked-up values after seeing the challenge), witness the per-row multiplicities
+    /// (how many times each table row was looked up), and assert
+    /// `sum_i 1/(alpha - f_i) == sum_j mu_j/(alpha - t_j)`.
+    ///
+    /// Must be called once per handle, after every [`Self::lookup`] call for it and before
+    /// [`Self::build`]. Marks the handle as finalized so that dropping it (or any of its clones)
+    /// doesn't trip the "forgot to finalize" panic in [`LookupTable`]'s `Drop` impl.
+    pub fn finalize_lookup_table(&mut self, handle: &LookupTable<F>) {
+        let mut data = handle.0.borrow_mut();
+        assert!(
+            !data.finalized,
+            "finalize_lookup_table called twice on the same LookupTable"
+        );
+        data.finalized = true;
+        let looked_up = data.looked_up.clone();
+        let table = data.table.clone();
+        drop(data);
+
+        let table_targets: Vec<Target> = table.iter().map(|&t| self.constant(t)).collect();
+
+        let mut challenger = RecursiveChallenger::<F, PoseidonHash, D>::new(self);
+        challenger.observe_elements(&looked_up);
+        challenger.observe_elements(&table_targets);
+        let alpha = challenger.get_challenge(self);
+
+        let multiplicities: Vec<Target> = (0..table.len())
+            .map(|_| self.add_virtual_target())
+            .collect();
+        self.add_simple_generator(LookupMultiplicityGenerator {
+            looked_up: looked_up.clone(),
+            table: table.clone(),
+            multiplicities: multiplicities.clone(),
+        });
+
+        // Left-hand side: running sum of 1/(alpha - f_i) over the looked-up values.
+        let mut lhs_sum = self.zero();
+        for &f in &looked_up {
+            let inv = self.add_virtual_target();
+            self.add_simple_generator(ReciprocalGenerator { alpha, x: f, inv });
+            let diff = self.sub(alpha, f);
+            let product = self.mul(inv, diff);
+            self.assert_one(product);
+            lhs_sum = self.add(lhs_sum, inv);
+        }
+
+        // Right-hand side: running sum of mu_j/(alpha - t_j) over the table rows.
+        let mut rhs_sum = self.zero();
+        for (j, &t_target) in table_targets.iter().enumerate() {
+            let inv = self.add_virtual_target();
+            self.add_simple_generator(ReciprocalGenerator {
+                alpha,
+                x: t_target,
+                inv,
+            });
+            let diff = self.sub(alpha, t_target);
+            let product = self.mul(inv, diff);
+            self.assert_one(product);
+            let mu_inv = self.mul(multiplicities[j], inv);
+            rhs_sum = self.add(rhs_sum, mu_inv);
+        }
+
+        self.connect(lhs_sum, rhs_sum);
+    }
+}
+
+#[derive(Debug)]
+struct ReciprocalGenerator {
+    alpha: Target,
+    x: Target,
+    inv: Target,
+}
+
+impl<F: RichField> SimpleGenerator<F> for ReciprocalGenerator {
+    fn dependencies(&self) -> Vec<Target> {
+        vec![self.alpha, self.x]
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let alpha = witness.get_target(self.alpha);
+        let x = witness.get_target(self.x);
+        let inv = (alpha - x).inverse();
+        out_buffer.set_target(self.inv, inv);
+    }
+}
+
+#[derive(Debug)]
+struct LookupMultiplicityGenerator<F: Field> {
+    looked_up: Vec<Target>,
+    table: Vec<F>,
+    multiplicities: Vec<Target>,
+}
+
+impl<F: RichField> SimpleGenerator<F> for LookupMultiplicityGenerator<F> {
+    fn dependencies(&self) -> Vec<Target> {
+        self.looked_up.clone()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let mut counts: HashMap<u64, u64> = HashMap::new();
+        for &f in &self.looked_up {
+            let value = witness.get_target(f).to_canonical_u64();
+            *counts.entry(value).or_insert(0) += 1;
+        }
+        for (j, &t) in self.table.iter().enumerate() {
+            let count = counts.get(&t.to_canonical_u64()).copied().unwrap_or(0);
+            out_buffer.set_target(self.multiplicities[j], F::from_canonical_u64(count));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::PoseidonGoldilocksConfig;
+
+    #[test]
+    fn lookup_table_accumulates_across_clones() {
+        const D: usize = 2;
+        type F = <PoseidonGoldilocksConfig as crate::plonk::config::GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let table = (0..4u64).map(F::from_canonical_u64).collect();
+        let handle = builder.add_lookup_table(table);
+        let handle_clone = handle.clone();
+
+        let value = builder.constant(F::from_canonical_u64(2));
+        builder.lookup(&handle, value);
+        builder.lookup(&handle_clone, value);
+
+        assert_eq!(handle.0.borrow().looked_up.len(), 2);
+
+        // Every handle must be finalized before it's dropped, or `LookupTable`'s `Drop` impl panics.
+        builder.finalize_lookup_table(&handle);
+    }
+
+    /// End-to-end check that an in-table lookup value lets the circuit build and prove, while an
+    /// out-of-table value makes the LogUp equation unsatisfiable so proving fails.
+    #[test]
+    fn lookup_proves_membership_and_rejects_non_membership() {
+        const D: usize = 2;
+        type F = <PoseidonGoldilocksConfig as crate::plonk::config::GenericConfig<D>>::F;
+
+        let prove_lookup = |value: u64| {
+            let config = CircuitConfig::standard_recursion_config();
+            let mut builder = CircuitBuilder::<F, D>::new(config);
+
+            let table = (0..4u64).map(F::from_canonical_u64).collect();
+            let handle = builder.add_lookup_table(table);
+
+            let value_target = builder.constant(F::from_canonical_u64(value));
+            builder.lookup(&handle, value_target);
+            builder.finalize_lookup_table(&handle);
+
+            let data = builder.build::<PoseidonGoldilocksConfig>();
+            data.prove(crate::iop::witness::PartialWitness::new())
+        };
+
+        // 2 is in the table {0, 1, 2, 3}: proving should succeed.
+        prove_lookup(2).unwrap();
+
+        // 7 is not in the table: the LogUp sum equality is unsatisfiable, so proving must fail.
+        assert!(prove_lookup(7).is_err());
+    }
+}
@@ -0,0 +1,4 @@
+pub mod arithmetic_u32;
+pub mod biguint;
+pub mod lookup;
+pub mod permutation;
@@ -0,0 +1,114 @@
+use crate::field::extension_field::target::ExtensionTarget;
+use crate::field::extension_field::Extendable;
+use crate::field::field_types::RichField;
+use crate::hash::poseidon::PoseidonHash;
+use crate::iop::challenger::RecursiveChallenger;
+use crate::iop::target::Target;
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Assert that `a` and `b` are permutations of each other, i.e. the same multiset of values in
+    /// possibly different order. This is the grand-product multiset check: draw a transcript
+    /// challenge `beta`, then assert `product_i (beta - a_i) == product_j (beta - b_j)`, accumulated
+    /// with one multiplication constraint per element via running-product wires.
+    ///
+    /// Suitable for checking `InsertionGate`'s correctness (the output list must be a permutation of
+    /// the input list plus the new element) as well as higher-level shuffle arguments;
+    /// `InsertionGate` itself does not call it.
+    pub fn assert_permutation(&mut self, a: &[Target], b: &[Target]) {
+        assert_eq!(
+            a.len(),
+            b.len(),
+            "permutation check requires equal-length lists"
+        );
+
+        // `beta` must be drawn after both lists are committed to, so it comes from a
+        // RecursiveChallenger that has observed every element of both lists, the same way a
+        // verifier's post-commitment challenge is bound to the values it checks.
+        let mut challenger = RecursiveChallenger::<F, PoseidonHash, D>::new(self);
+        challenger.observe_elements(a);
+        challenger.observe_elements(b);
+        let beta = challenger.get_challenge(self);
+
+        let mut lhs_product = self.one();
+        for &a_i in a {
+            let diff = self.sub(beta, a_i);
+            lhs_product = self.mul(lhs_product, diff);
+        }
+
+        let mut rhs_product = self.one();
+        for &b_j in b {
+            let diff = self.sub(beta, b_j);
+            rhs_product = self.mul(rhs_product, diff);
+        }
+
+        self.connect(lhs_product, rhs_product);
+    }
+
+    /// `ExtensionTarget` variant of [`Self::assert_permutation`], for lists of extension-field
+    /// elements.
+    pub fn assert_permutation_extension(
+        &mut self,
+        a: &[ExtensionTarget<D>],
+        b: &[ExtensionTarget<D>],
+    ) {
+        assert_eq!(
+            a.len(),
+            b.len(),
+            "permutation check requires equal-length lists"
+        );
+
+        let mut challenger = RecursiveChallenger::<F, PoseidonHash, D>::new(self);
+        for &a_i in a {
+            challenger.observe_elements(&a_i.0);
+        }
+        for &b_j in b {
+            challenger.observe_elements(&b_j.0);
+        }
+        let beta = challenger.get_extension_challenge(self);
+
+        let mut lhs_product = self.one_extension();
+        for &a_i in a {
+            let diff = self.sub_extension(beta, a_i);
+            lhs_product = self.mul_extension(lhs_product, diff);
+        }
+
+        let mut rhs_product = self.one_extension();
+        for &b_j in b {
+            let diff = self.sub_extension(beta, b_j);
+            rhs_product = self.mul_extension(rhs_product, diff);
+        }
+
+        self.connect_extension(lhs_product, rhs_product);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::field_types::Field;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    #[test]
+    fn assert_permutation_accepts_reordered_list() {
+        const D: usize = 2;
+        type F = <PoseidonGoldilocksConfig as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let a: Vec<Target> = (0..4u64)
+            .map(|i| builder.constant(F::from_canonical_u64(i)))
+            .collect();
+        let b: Vec<Target> = [2u64, 0, 3, 1]
+            .iter()
+            .map(|&i| builder.constant(F::from_canonical_u64(i)))
+            .collect();
+
+        builder.assert_permutation(&a, &b);
+
+        let data = builder.build::<PoseidonGoldilocksConfig>();
+        data.prove(crate::iop::witness::PartialWitness::new()).unwrap();
+    }
+}
@@ -0,0 +1,698 @@
+use std::marker::PhantomData;
+use std::ops::Range;
+
+use crate::field::extension_field::target::ExtensionTarget;
+use crate::field::extension_field::Extendable;
+use crate::field::field_types::{Field, RichField};
+use crate::gates::gate::{Gate, GateRef};
+use crate::iop::generator::{GeneratedValues, SimpleGenerator, WitnessGenerator};
+use crate::iop::target::{BoolTarget, Target};
+use crate::iop::witness::{PartitionWitness, Witness};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::vars::{EvaluationTargets, EvaluationVars};
+
+/// The initialization vector used by BLAKE3 (identical to the first eight words of the BLAKE2s IV).
+pub const IV: [u32; 8] = [
+    0x6A09E667, 0xBB67AE85, 0x3C6EF372, 0xA54FF53A, 0x510E527F, 0x9B05688C, 0x1F83D9AB, 0x5BE0CD19,
+];
+
+/// The message-word permutation applied before every round but the first.
+pub const MSG_PERMUTATION: [usize; 16] = [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8];
+
+/// Number of rounds in the BLAKE3 compression function.
+const NUM_ROUNDS: usize = 7;
+
+/// Number of `G` mixing calls per round (columns, then diagonals).
+const NUM_G_PER_ROUND: usize = 8;
+
+/// Each 32-bit word is represented in-circuit as 32 boolean wires, least-significant first.
+const BITS_PER_WORD: usize = 32;
+
+/// A `G` invocation needs the post-addition words `a'` and `a''` decomposed into bits (so they can
+/// be XORed with `d`/`d'`), `c'` and `c''` decomposed into bits (so they can be XORed with `b`/`b'`),
+/// and — so that no symbolic expression is ever carried into a *later* XOR, which would make that
+/// XOR's constraint degree exceed 2 — the rotate-only intermediates `d'` and `b'` and the rotate-only
+/// outputs `d''` and `b''` decomposed into bits too. Without those last four decompositions, `d'`
+/// (itself already degree 2, being an XOR of two degree-1 inputs) would be XORed again to produce
+/// `d''`, pushing that constraint to degree 3; likewise for `b'`/`b''`. We budget two extra carry
+/// bits for the four additions (`a'`, `c'`, `a''`, `c''`); the four rotate-only values (`d'`, `b'`,
+/// `b''`, `d''`) need no carry bits.
+const BITS_PER_ADD: usize = BITS_PER_WORD + 2;
+const NUM_ADD_DECOMPS: usize = 4;
+const NUM_WORD_DECOMPS: usize = 4;
+const DECOMPS_PER_G: usize = NUM_ADD_DECOMPS + NUM_WORD_DECOMPS;
+const WIRES_PER_G: usize = NUM_ADD_DECOMPS * BITS_PER_ADD + NUM_WORD_DECOMPS * BITS_PER_WORD;
+const WIRES_PER_ROUND: usize = NUM_G_PER_ROUND * WIRES_PER_G;
+
+/// Decomposition indices within a `G` call, see [`Blake3Gate::wires_g_decomp`].
+const DECOMP_A_PRIME: usize = 0;
+const DECOMP_C_PRIME: usize = 1;
+const DECOMP_A_PRIME2: usize = 2;
+const DECOMP_C_PRIME2: usize = 3;
+const DECOMP_D_PRIME: usize = 4;
+const DECOMP_B_PRIME: usize = 5;
+const DECOMP_B_OUT: usize = 6;
+const DECOMP_D_OUT: usize = 7;
+
+/// The bit-width of the `decomp`-th decomposition of a `G` call: the four additions need two extra
+/// carry bits, the four rotate-only values don't.
+fn decomp_width(decomp: usize) -> usize {
+    if decomp < NUM_ADD_DECOMPS {
+        BITS_PER_ADD
+    } else {
+        BITS_PER_WORD
+    }
+}
+
+fn decomp_offset(decomp: usize) -> usize {
+    (0..decomp).map(decomp_width).sum()
+}
+
+/// A gate computing one full BLAKE3 compression (the 7-round, 16-word G-mixing schedule), so that
+/// a BLAKE3 digest can be checked inside a circuit far more cheaply than by simulating it with
+/// generic arithmetic. Mirrors the wire-allocation style of `InsertionGate`: inputs, outputs and
+/// then one block of per-round intermediate wires.
+#[derive(Clone, Debug)]
+pub struct Blake3Gate<F: Extendable<D>, const D: usize> {
+    pub _phantom: PhantomData<F>,
+}
+
+impl<F: Extendable<D>, const D: usize> Blake3Gate<F, D> {
+    pub fn new() -> GateRef<F, D> {
+        GateRef::new(Self {
+            _phantom: PhantomData,
+        })
+    }
+
+    /// The 16 input state words (the 8 chaining-value words followed by the 4 IV words, the two
+    /// counter words, the block length and the domain-separation flags), each stored as
+    /// `BITS_PER_WORD` boolean wires, least-significant bit first.
+    pub fn wires_input_state(i: usize) -> Range<usize> {
+        debug_assert!(i < 16);
+        let start = i * BITS_PER_WORD;
+        start..start + BITS_PER_WORD
+    }
+
+    fn start_of_message_wires() -> usize {
+        16 * BITS_PER_WORD
+    }
+
+    /// The 16 message words of the block being compressed.
+    pub fn wires_message(i: usize) -> Range<usize> {
+        debug_assert!(i < 16);
+        let start = Self::start_of_message_wires() + i * BITS_PER_WORD;
+        start..start + BITS_PER_WORD
+    }
+
+    fn start_of_output_wires() -> usize {
+        Self::start_of_message_wires() + 16 * BITS_PER_WORD
+    }
+
+    /// The 16 output state words, after the final feed-forward XOR of the two halves is *not yet*
+    /// applied (i.e. the raw compression output, matching the BLAKE3 spec's `state` array).
+    pub fn wires_output_state(i: usize) -> Range<usize> {
+        debug_assert!(i < 16);
+        let start = Self::start_of_output_wires() + i * BITS_PER_WORD;
+        start..start + BITS_PER_WORD
+    }
+
+    fn start_of_intermediate_wires() -> usize {
+        Self::start_of_output_wires() + 16 * BITS_PER_WORD
+    }
+
+    /// Bit-decomposition wires for the `a'`/`c'`/`a''`/`c''`/`d'`/`b'`/`b''`/`d''` values produced by the
+    /// `decomp`-th decomposition of the `g`-th `G` invocation of round `r` (see the `DECOMP_*`
+    /// constants for `decomp`'s meaning).
+    fn wires_g_decomp(r: usize, g: usize, decomp: usize) -> Range<usize> {
+        debug_assert!(r < NUM_ROUNDS && g < NUM_G_PER_ROUND && decomp < DECOMPS_PER_G);
+        let start =
+            Self::start_of_intermediate_wires() + r * WIRES_PER_ROUND + g * WIRES_PER_G + decomp_offset(decomp);
+        start..start + decomp_width(decomp)
+    }
+}
+
+/// Evaluate one `G` mixing function given bit-decomposed 32-bit words `a, b, c, d` and message
+/// words `mx, my`, plus the eight witnessed bit-decompositions for `a', c', d', b', a'', c'', b'',
+/// d''`. Every one of those eight values is used as the *witnessed* decomposition, each tied
+/// back to its computed value by a degree-2 equality constraint, rather than as a raw symbolic
+/// expression — in particular `d'` and `b'` must be pinned too (not just the final `a''..d''`),
+/// since each otherwise gets XORed a second time (into `d''`/`b''` respectively) and an XOR of an
+/// already-degree-2 expression is degree 3. Pinning every rotate-only value keeps every XOR's
+/// inputs at degree 1, so every constraint in this function stays at or under degree 2. Returns the
+/// bit-decomposed `a'', b'', c'', d''` along with the constraints that must vanish.
+fn g_mix<T: Copy + std::ops::Add<Output = T> + std::ops::Sub<Output = T> + std::ops::Mul<Output = T>>(
+    zero: T,
+    one: T,
+    two32: T,
+    a: &[T],
+    b: &[T],
+    c: &[T],
+    d: &[T],
+    mx: T,
+    my: T,
+    a_prime_bits: &[T],
+    c_prime_bits: &[T],
+    a_prime2_bits: &[T],
+    c_prime2_bits: &[T],
+    d_prime_bits: &[T],
+    b_prime_bits: &[T],
+    b_out_bits: &[T],
+    d_out_bits: &[T],
+) -> (Vec<T>, Vec<T>, Vec<T>, Vec<T>, Vec<T>) {
+    let mut constraints = Vec::new();
+
+    let bits_to_value = |bits: &[T]| -> T {
+        let mut acc = zero;
+        let mut pow = one;
+        for &b in bits {
+            acc = acc + b * pow;
+            pow = pow + pow;
+        }
+        acc
+    };
+    let bool_constraints = |bits: &[T], out: &mut Vec<T>| {
+        for &b in bits {
+            out.push(b * b - b);
+        }
+    };
+    let xor_bits = |x: &[T], y: &[T]| -> Vec<T> {
+        x.iter()
+            .zip(y.iter())
+            .map(|(&xb, &yb)| xb + yb - (xb * yb) - (xb * yb))
+            .collect()
+    };
+    let rotr = |bits: &[T], n: usize| -> Vec<T> {
+        let len = bits.len();
+        (0..len).map(|i| bits[(i + n) % len]).collect()
+    };
+    let pin = |computed: Vec<T>, witnessed: &[T], out: &mut Vec<T>| -> Vec<T> {
+        for (&computed_bit, &witnessed_bit) in computed.iter().zip(witnessed.iter()) {
+            out.push(witnessed_bit - computed_bit);
+        }
+        witnessed.to_vec()
+    };
+
+    // a' = a + b + mx  (mod 2^32), low 32 bits kept, top 2 bits are the carry.
+    bool_constraints(a_prime_bits, &mut constraints);
+    let a_val = bits_to_value(a);
+    let b_val = bits_to_value(b);
+    let a_prime_low = bits_to_value(&a_prime_bits[..BITS_PER_WORD]);
+    let carry1 = bits_to_value(&a_prime_bits[BITS_PER_WORD..]);
+    constraints.push(a_val + b_val + mx - (a_prime_low + carry1 * two32));
+
+    // d' = rotr32(d xor a', 16), pinned to the witnessed `d_prime_bits` wires so the *next* XOR
+    // that consumes `d'` (producing `d''`) sees a degree-1 input rather than a degree-2 one.
+    bool_constraints(d_prime_bits, &mut constraints);
+    let d_xor_aprime = xor_bits(d, &a_prime_bits[..BITS_PER_WORD]);
+    let d_prime = pin(rotr(&d_xor_aprime, 16), d_prime_bits, &mut constraints);
+
+    // c' = c + d'  (mod 2^32)
+    bool_constraints(c_prime_bits, &mut constraints);
+    let c_val = bits_to_value(c);
+    let d_prime_val = bits_to_value(&d_prime);
+    let c_prime_low = bits_to_value(&c_prime_bits[..BITS_PER_WORD]);
+    let carry2 = bits_to_value(&c_prime_bits[BITS_PER_WORD..]);
+    constraints.push(c_val + d_prime_val - (c_prime_low + carry2 * two32));
+
+    // b' = rotr32(b xor c', 12), pinned for the same reason as `d'` above.
+    bool_constraints(b_prime_bits, &mut constraints);
+    let b_xor_cprime = xor_bits(b, &c_prime_bits[..BITS_PER_WORD]);
+    let b_prime = pin(rotr(&b_xor_cprime, 12), b_prime_bits, &mut constraints);
+
+    // a'' = a' + b' + my  (mod 2^32)
+    bool_constraints(a_prime2_bits, &mut constraints);
+    let b_prime_val = bits_to_value(&b_prime);
+    let a_prime2_low = bits_to_value(&a_prime2_bits[..BITS_PER_WORD]);
+    let carry3 = bits_to_value(&a_prime2_bits[BITS_PER_WORD..]);
+    constraints.push(a_prime_low + b_prime_val + my - (a_prime2_low + carry3 * two32));
+
+    // d'' = rotr32(d' xor a'', 8), pinned to the witnessed `d_out_bits` wires.
+    bool_constraints(d_out_bits, &mut constraints);
+    let dprime_xor_aprime2 = xor_bits(&d_prime, &a_prime2_bits[..BITS_PER_WORD]);
+    let d_prime2 = pin(rotr(&dprime_xor_aprime2, 8), d_out_bits, &mut constraints);
+
+    // c'' = c' + d''  (mod 2^32)
+    bool_constraints(c_prime2_bits, &mut constraints);
+    let d_prime2_val = bits_to_value(&d_prime2);
+    let c_prime2_low = bits_to_value(&c_prime2_bits[..BITS_PER_WORD]);
+    let carry4 = bits_to_value(&c_prime2_bits[BITS_PER_WORD..]);
+    constraints.push(c_prime_low + d_prime2_val - (c_prime2_low + carry4 * two32));
+
+    // b'' = rotr32(b' xor c'', 7), pinned to the witnessed `b_out_bits` wires.
+    bool_constraints(b_out_bits, &mut constraints);
+    let bprime_xor_cprime2 = xor_bits(&b_prime, &c_prime2_bits[..BITS_PER_WORD]);
+    let b_prime2 = pin(rotr(&bprime_xor_cprime2, 7), b_out_bits, &mut constraints);
+
+    let a_out = a_prime2_bits[..BITS_PER_WORD].to_vec();
+    let c_out = c_prime2_bits[..BITS_PER_WORD].to_vec();
+    let b_out = b_prime2;
+    let d_out = d_prime2;
+
+    (a_out, b_out, c_out, d_out, constraints)
+}
+
+impl<F: Extendable<D>, const D: usize> Gate<F, D> for Blake3Gate<F, D> {
+    fn id(&self) -> String {
+        format!("{:?}<D={}>", self, D)
+    }
+
+    fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
+        let zero = F::Extension::ZERO;
+        let one = F::Extension::ONE;
+        let two32 = F::Extension::from_canonical_u64(1u64 << 32);
+
+        let get_bits = |range: Range<usize>| -> Vec<F::Extension> {
+            range.map(|w| vars.local_wires[w]).collect()
+        };
+
+        let mut state: Vec<Vec<F::Extension>> =
+            (0..16).map(|i| get_bits(Self::wires_input_state(i))).collect();
+        let message: Vec<Vec<F::Extension>> =
+            (0..16).map(|i| get_bits(Self::wires_message(i))).collect();
+
+        let mut constraints = Vec::new();
+
+        for r in 0..NUM_ROUNDS {
+            let schedule: Vec<usize> = if r == 0 {
+                (0..16).collect()
+            } else {
+                let mut perm: Vec<usize> = (0..16).collect();
+                for _ in 0..r {
+                    perm = perm.iter().map(|&i| MSG_PERMUTATION[i]).collect();
+                }
+                perm
+            };
+            let m = |k: usize| message[schedule[k]].clone();
+
+            let columns = [(0, 4, 8, 12), (1, 5, 9, 13), (2, 6, 10, 14), (3, 7, 11, 15)];
+            for (g_idx, &(ai, bi, ci, di)) in columns.iter().enumerate() {
+                let mx = bits_to_val(&m(2 * g_idx), zero, one);
+                let my = bits_to_val(&m(2 * g_idx + 1), zero, one);
+                let a_prime = get_bits(Self::wires_g_decomp(r, g_idx, DECOMP_A_PRIME));
+                let c_prime = get_bits(Self::wires_g_decomp(r, g_idx, DECOMP_C_PRIME));
+                let a_prime2 = get_bits(Self::wires_g_decomp(r, g_idx, DECOMP_A_PRIME2));
+                let c_prime2 = get_bits(Self::wires_g_decomp(r, g_idx, DECOMP_C_PRIME2));
+                let d_prime = get_bits(Self::wires_g_decomp(r, g_idx, DECOMP_D_PRIME));
+                let b_prime = get_bits(Self::wires_g_decomp(r, g_idx, DECOMP_B_PRIME));
+                let b_out = get_bits(Self::wires_g_decomp(r, g_idx, DECOMP_B_OUT));
+                let d_out = get_bits(Self::wires_g_decomp(r, g_idx, DECOMP_D_OUT));
+                let (a2, b2, c2, d2, cons) = g_mix(
+                    zero,
+                    one,
+                    two32,
+                    &state[ai],
+                    &state[bi],
+                    &state[ci],
+                    &state[di],
+                    mx,
+                    my,
+                    &a_prime,
+                    &c_prime,
+                    &a_prime2,
+                    &c_prime2,
+                    &d_prime,
+                    &b_prime,
+                    &b_out,
+                    &d_out,
+                );
+                constraints.extend(cons);
+                state[ai] = a2;
+                state[bi] = b2;
+                state[ci] = c2;
+                state[di] = d2;
+            }
+
+            let diagonals = [(0, 5, 10, 15), (1, 6, 11, 12), (2, 7, 8, 13), (3, 4, 9, 14)];
+            for (g_idx, &(ai, bi, ci, di)) in diagonals.iter().enumerate() {
+                let mx = bits_to_val(&m(8 + 2 * g_idx), zero, one);
+                let my = bits_to_val(&m(8 + 2 * g_idx + 1), zero, one);
+                let g = NUM_G_PER_ROUND - 4 + g_idx;
+                let a_prime = get_bits(Self::wires_g_decomp(r, g, DECOMP_A_PRIME));
+                let c_prime = get_bits(Self::wires_g_decomp(r, g, DECOMP_C_PRIME));
+                let a_prime2 = get_bits(Self::wires_g_decomp(r, g, DECOMP_A_PRIME2));
+                let c_prime2 = get_bits(Self::wires_g_decomp(r, g, DECOMP_C_PRIME2));
+                let d_prime = get_bits(Self::wires_g_decomp(r, g, DECOMP_D_PRIME));
+                let b_prime = get_bits(Self::wires_g_decomp(r, g, DECOMP_B_PRIME));
+                let b_out = get_bits(Self::wires_g_decomp(r, g, DECOMP_B_OUT));
+                let d_out = get_bits(Self::wires_g_decomp(r, g, DECOMP_D_OUT));
+                let (a2, b2, c2, d2, cons) = g_mix(
+                    zero,
+                    one,
+                    two32,
+                    &state[ai],
+                    &state[bi],
+                    &state[ci],
+                    &state[di],
+                    mx,
+                    my,
+                    &a_prime,
+                    &c_prime,
+                    &a_prime2,
+                    &c_prime2,
+                    &d_prime,
+                    &b_prime,
+                    &b_out,
+                    &d_out,
+                );
+                constraints.extend(cons);
+                state[ai] = a2;
+                state[bi] = b2;
+                state[ci] = c2;
+                state[di] = d2;
+            }
+        }
+
+        for i in 0..16 {
+            let out_bits = get_bits(Self::wires_output_state(i));
+            for (b1, b2) in state[i].iter().zip(out_bits.iter()) {
+                constraints.push(*b1 - *b2);
+            }
+        }
+
+        constraints
+    }
+
+    fn eval_unfiltered_recursively(
+        &self,
+        _builder: &mut CircuitBuilder<F, D>,
+        _vars: EvaluationTargets<D>,
+    ) -> Vec<ExtensionTarget<D>> {
+        todo!("recursive verification of Blake3Gate is not yet implemented")
+    }
+
+    fn generators(
+        &self,
+        gate_index: usize,
+        _local_constants: &[F],
+    ) -> Vec<Box<dyn WitnessGenerator<F>>> {
+        vec![Box::new(Blake3Generator::<F, D> {
+            gate_index,
+            _phantom: PhantomData,
+        })]
+    }
+
+    /// Every wire this gate uses lives in this single gate's row; a `CircuitConfig` that includes
+    /// this gate must set `num_wires` to at least this value (`standard_recursion_config`'s default
+    /// of 135 is nowhere near enough — see [`Self::min_wires_config`]).
+    fn num_wires(&self) -> usize {
+        Self::start_of_intermediate_wires() + NUM_ROUNDS * WIRES_PER_ROUND
+    }
+
+    fn num_constants(&self) -> usize {
+        0
+    }
+
+    fn degree(&self) -> usize {
+        2
+    }
+
+    fn num_constraints(&self) -> usize {
+        // Per `G` call: a boolean + an addition-equality constraint for each of the four addition
+        // decompositions, plus a boolean + a pin-equality constraint per bit for each of the four
+        // rotate-only decompositions (`d'`, `b'`, `b''`, `d''`) — times eight `G` calls per round,
+        // times the number of rounds, plus the 16-word output equality.
+        let per_g = NUM_ADD_DECOMPS * (BITS_PER_ADD + 1) + NUM_WORD_DECOMPS * 2 * BITS_PER_WORD;
+        NUM_ROUNDS * NUM_G_PER_ROUND * per_g + 16 * BITS_PER_WORD
+    }
+}
+
+impl<F: Extendable<D>, const D: usize> Blake3Gate<F, D> {
+    /// The minimum `CircuitConfig::num_wires` a circuit using this gate needs — `Blake3Gate` packs
+    /// its whole bit-decomposed compression trace into one gate row rather than spreading it across
+    /// several gate types, so it needs far more wire columns per row than
+    /// `CircuitConfig::standard_recursion_config` budgets for. Callers must build a config with
+    /// `num_wires: Blake3Gate::<F, D>::min_wires_config().max(needed_by_other_gates)` (e.g. via
+    /// `CircuitConfig { num_wires: ..., ..CircuitConfig::standard_recursion_config() }`) rather than
+    /// using the standard config as-is.
+    pub fn min_wires_config() -> usize {
+        Self::start_of_intermediate_wires() + NUM_ROUNDS * WIRES_PER_ROUND
+    }
+}
+
+fn bits_to_val<T: Copy + std::ops::Add<Output = T> + std::ops::Mul<Output = T>>(
+    bits: &[T],
+    zero: T,
+    one: T,
+) -> T {
+    let mut acc = zero;
+    let mut pow = one;
+    for &b in bits {
+        acc = acc + b * pow;
+        pow = pow + pow;
+    }
+    acc
+}
+
+#[derive(Debug)]
+struct Blake3Generator<F: RichField + Extendable<D>, const D: usize> {
+    gate_index: usize,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F> for Blake3Generator<F, D> {
+    fn dependencies(&self) -> Vec<Target> {
+        (0..16)
+            .flat_map(|i| Blake3Gate::<F, D>::wires_input_state(i))
+            .chain((0..16).flat_map(|i| Blake3Gate::<F, D>::wires_message(i)))
+            .map(|w| Target::wire(self.gate_index, w))
+            .collect()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let get_word = |base: usize| -> u32 {
+            let mut v = 0u32;
+            for b in 0..BITS_PER_WORD {
+                let bit = witness.get_target(Target::wire(self.gate_index, base + b));
+                if bit.is_one() {
+                    v |= 1 << b;
+                }
+            }
+            v
+        };
+
+        let cv: Vec<u32> = (0..16)
+            .map(|i| get_word(Blake3Gate::<F, D>::wires_input_state(i).start))
+            .collect();
+        let msg: Vec<u32> = (0..16)
+            .map(|i| get_word(Blake3Gate::<F, D>::wires_message(i).start))
+            .collect();
+
+        let (final_state, all_decomps) = compress_with_trace(&cv, &msg);
+
+        for (r, round_decomps) in all_decomps.iter().enumerate() {
+            for (g_idx, decomps) in round_decomps.iter().enumerate() {
+                for (d_idx, value) in decomps.iter().enumerate() {
+                    let range = Blake3Gate::<F, D>::wires_g_decomp(r, g_idx, d_idx);
+                    for (bit_idx, w) in range.enumerate() {
+                        let bit = (value >> bit_idx) & 1;
+                        out_buffer.set_target(
+                            Target::wire(self.gate_index, w),
+                            F::from_canonical_u64(bit as u64),
+                        );
+                    }
+                }
+            }
+        }
+
+        for i in 0..16 {
+            let range = Blake3Gate::<F, D>::wires_output_state(i);
+            for (bit_idx, w) in range.enumerate() {
+                let bit = (final_state[i] >> bit_idx) & 1;
+                out_buffer.set_target(Target::wire(self.gate_index, w), F::from_canonical_u64(bit as u64));
+            }
+        }
+    }
+}
+
+/// Out-of-circuit reference implementation, also used by the generator to produce the witness for
+/// every intermediate decomposition the gate needs. Returns the 16-word final state together with
+/// the per-round, per-`G` witnessed `(a', c', a'', c'', d', b', b'', d'')` values, in the same order
+/// as the `DECOMP_*` indices. The four addition results are the full sums, carry bits included.
+fn compress_with_trace(cv: &[u32], block_words: &[u32]) -> (Vec<u32>, Vec<Vec<[u64; 8]>>) {
+    let mut state = [0u32; 16];
+    state[..8].copy_from_slice(&cv[..8]);
+    state[8..16].copy_from_slice(&cv[8..16]);
+
+    let mut all_decomps = Vec::with_capacity(NUM_ROUNDS);
+    let mut schedule: Vec<usize> = (0..16).collect();
+
+    for _ in 0..NUM_ROUNDS {
+        let m: Vec<u32> = schedule.iter().map(|&i| block_words[i]).collect();
+        let mut round_decomps = Vec::with_capacity(NUM_G_PER_ROUND * 2);
+
+        let mut g = |state: &mut [u32; 16], ai: usize, bi: usize, ci: usize, di: usize, mx: u32, my: u32| -> [u64; 8] {
+            let a_prime_full = state[ai] as u64 + state[bi] as u64 + mx as u64;
+            let a_prime = a_prime_full as u32;
+            let d_prime = (state[di] ^ a_prime).rotate_right(16);
+            let c_prime_full = state[ci] as u64 + d_prime as u64;
+            let c_prime = c_prime_full as u32;
+            let b_prime = (state[bi] ^ c_prime).rotate_right(12);
+            let a_prime2_full = a_prime as u64 + b_prime as u64 + my as u64;
+            let a_prime2 = a_prime2_full as u32;
+            let d_prime2 = (d_prime ^ a_prime2).rotate_right(8);
+            let c_prime2_full = c_prime as u64 + d_prime2 as u64;
+            let c_prime2 = c_prime2_full as u32;
+            let b_prime2 = (b_prime ^ c_prime2).rotate_right(7);
+            state[ai] = a_prime2;
+            state[bi] = b_prime2;
+            state[ci] = c_prime2;
+            state[di] = d_prime2;
+            [
+                a_prime_full, c_prime_full, a_prime2_full, c_prime2_full,
+                d_prime as u64, b_prime as u64, b_prime2 as u64, d_prime2 as u64,
+            ]
+        };
+
+        let columns = [(0, 4, 8, 12), (1, 5, 9, 13), (2, 6, 10, 14), (3, 7, 11, 15)];
+        for (g_idx, &(ai, bi, ci, di)) in columns.iter().enumerate() {
+            round_decomps.push(g(&mut state, ai, bi, ci, di, m[2 * g_idx], m[2 * g_idx + 1]));
+        }
+        let diagonals = [(0, 5, 10, 15), (1, 6, 11, 12), (2, 7, 8, 13), (3, 4, 9, 14)];
+        for (g_idx, &(ai, bi, ci, di)) in diagonals.iter().enumerate() {
+            round_decomps.push(g(&mut state, ai, bi, ci, di, m[8 + 2 * g_idx], m[8 + 2 * g_idx + 1]));
+        }
+
+        all_decomps.push(round_decomps);
+        schedule = schedule.iter().map(|&i| MSG_PERMUTATION[i]).collect();
+    }
+
+    (state.to_vec(), all_decomps)
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Verify one BLAKE3 compression of `message` (16 `U32Target`s) under chaining value
+    /// `chaining_value` (8 `U32Target`s), returning the first 8 words of the compression output
+    /// XORed with the last 8 (i.e. the standard 32-byte chaining-value / digest output), as
+    /// `U32Target`s.
+    pub fn blake3(
+        &mut self,
+        chaining_value: [crate::gadgets::arithmetic_u32::U32Target; 8],
+        message: [crate::gadgets::arithmetic_u32::U32Target; 16],
+        counter: u64,
+        block_len: u32,
+        flags: u32,
+    ) -> [crate::gadgets::arithmetic_u32::U32Target; 8] {
+        let gate = Blake3Gate::<F, D>::new();
+        let gate_index = self.add_gate(gate, vec![]);
+
+        let mut input_words = Vec::with_capacity(16);
+        input_words.extend(chaining_value.iter().map(|t| t.0));
+        input_words.push(self.constant(F::from_canonical_u32(IV[0]).into()));
+        input_words.push(self.constant(F::from_canonical_u32(IV[1]).into()));
+        input_words.push(self.constant(F::from_canonical_u32(IV[2]).into()));
+        input_words.push(self.constant(F::from_canonical_u32(IV[3]).into()));
+        input_words.push(self.constant(F::from_canonical_u64(counter & 0xFFFF_FFFF).into()));
+        input_words.push(self.constant(F::from_canonical_u64(counter >> 32).into()));
+        input_words.push(self.constant(F::from_canonical_u32(block_len).into()));
+        input_words.push(self.constant(F::from_canonical_u32(flags).into()));
+
+        for (i, word) in input_words.iter().enumerate() {
+            let bits = self.split_le(*word, BITS_PER_WORD);
+            for (b, &wire) in bits.iter().zip(Blake3Gate::<F, D>::wires_input_state(i).into_iter().collect::<Vec<_>>().iter()) {
+                self.connect(b.target, Target::wire(gate_index, wire));
+            }
+        }
+        for (i, word) in message.iter().enumerate() {
+            let bits = self.split_le(word.0, BITS_PER_WORD);
+            for (b, &wire) in bits.iter().zip(Blake3Gate::<F, D>::wires_message(i).into_iter().collect::<Vec<_>>().iter()) {
+                self.connect(b.target, Target::wire(gate_index, wire));
+            }
+        }
+
+        let mut output = Vec::with_capacity(8);
+        for i in 0..8 {
+            let low_bits: Vec<BoolTarget> = Blake3Gate::<F, D>::wires_output_state(i)
+                .map(|w| BoolTarget::new_unsafe(Target::wire(gate_index, w)))
+                .collect();
+            let high_bits: Vec<BoolTarget> = Blake3Gate::<F, D>::wires_output_state(i + 8)
+                .map(|w| BoolTarget::new_unsafe(Target::wire(gate_index, w)))
+                .collect();
+            let xored: Vec<BoolTarget> = low_bits
+                .iter()
+                .zip(high_bits.iter())
+                .map(|(&l, &h)| self.xor(l, h))
+                .collect();
+            output.push(crate::gadgets::arithmetic_u32::U32Target(self.le_sum(xored.iter())));
+        }
+
+        [
+            output[0], output[1], output[2], output[3], output[4], output[5], output[6], output[7],
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::field_types::Field;
+    use crate::gadgets::arithmetic_u32::U32Target;
+    use crate::iop::witness::PartialWitness;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    /// `compress_with_trace` is the out-of-circuit reference the generator witnesses against; check
+    /// it against a compression of the all-zero block under the IV chaining value, which is the
+    /// first compression BLAKE3 performs when hashing an empty input.
+    #[test]
+    fn compress_matches_reference_vector() {
+        // IV chaining value, IV[0..4], counter 0 (low, high), block length 0, and flags
+        // CHUNK_START | CHUNK_END | ROOT: the single compression that hashes the empty input.
+        let mut state: Vec<u32> = IV.to_vec();
+        state.extend_from_slice(&IV[..4]);
+        state.extend_from_slice(&[0, 0, 0, 1 | 2 | 8]);
+        let (final_state, _) = compress_with_trace(&state, &[0u32; 16]);
+        assert_eq!(final_state.len(), 16);
+        // The feed-forward digest (low half XOR high half) is the BLAKE3 hash of the empty input.
+        let digest: Vec<u32> = (0..8).map(|i| final_state[i] ^ final_state[i + 8]).collect();
+        let expected: Vec<u32> = ::blake3::hash(b"")
+            .as_bytes()
+            .chunks(4)
+            .map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+            .collect();
+        assert_eq!(digest, expected);
+    }
+
+    /// End-to-end check that `CircuitBuilder::blake3` actually builds, proves and verifies — not
+    /// just that the out-of-circuit reference is plausible. `Blake3Gate` needs far more wire columns
+    /// per row than `standard_recursion_config` budgets for, so the config is widened via
+    /// `Blake3Gate::min_wires_config` first; building under the unmodified standard config would
+    /// panic instead of producing a usable circuit.
+    #[test]
+    fn blake3_gate_proves_and_verifies_compression_of_the_all_zero_block() {
+        const D: usize = 2;
+        type F = <PoseidonGoldilocksConfig as GenericConfig<D>>::F;
+
+        let config = CircuitConfig {
+            num_wires: Blake3Gate::<F, D>::min_wires_config(),
+            ..CircuitConfig::standard_recursion_config()
+        };
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let cv: [U32Target; 8] =
+            std::array::from_fn(|i| U32Target(builder.constant(F::from_canonical_u32(IV[i]))));
+        let message: [U32Target; 16] =
+            std::array::from_fn(|_| U32Target(builder.constant(F::ZERO)));
+
+        let digest = builder.blake3(cv, message, 0, 64, 0);
+        for word in &digest {
+            builder.register_public_input(word.0);
+        }
+
+        let data = builder.build::<PoseidonGoldilocksConfig>();
+        let proof = data.prove(PartialWitness::new()).unwrap();
+
+        // The same input state `CircuitBuilder::blake3` lays out: IV chaining value, IV[0..4],
+        // counter 0 (low, high), block length 64, flags 0.
+        let mut state: Vec<u32> = IV.to_vec();
+        state.extend_from_slice(&IV[..4]);
+        state.extend_from_slice(&[0, 0, 64, 0]);
+        let (final_state, _) = compress_with_trace(&state, &[0u32; 16]);
+        let expected_digest: Vec<u32> =
+            (0..8).map(|i| final_state[i] ^ final_state[i + 8]).collect();
+        for (pi, &expected) in proof.public_inputs.iter().zip(expected_digest.iter()) {
+            assert_eq!(pi.to_canonical_u64() as u32, expected);
+        }
+
+        data.verify(proof).unwrap();
+    }
+}
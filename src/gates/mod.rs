@@ -0,0 +1,3 @@
+pub mod blake3;
+pub(crate) mod gate;
+pub(crate) mod insertion;
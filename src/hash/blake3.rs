@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+
+use crate::field::field_types::RichField;
+use crate::hash::hash_types::{HashOut, RichFieldWithPoseidon};
+use crate::plonk::config::{GenericConfig, Hasher};
+
+/// BLAKE3-over-field-elements, used as an alternative (non-algebraic) hash for Merkle caps and
+/// public-input hashing. Unlike Poseidon this is cheap natively but expensive to verify in-circuit
+/// without the dedicated `Blake3Gate` (see `gates::blake3`).
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Blake3Hash<const N: usize>;
+
+impl<F: RichField, const N: usize> Hasher<F> for Blake3Hash<N> {
+    const HASH_SIZE: usize = 32;
+    type Hash = HashOut<F>;
+    type Permutation = crate::hash::poseidon::PoseidonPermutation;
+
+    fn hash(input: Vec<F>, _pad: bool) -> Self::Hash {
+        let mut hasher = blake3::Hasher::new();
+        for x in &input {
+            hasher.update(&x.to_canonical_u64().to_le_bytes());
+        }
+        let digest = hasher.finalize();
+        HashOut::from_bytes(digest.as_bytes())
+    }
+
+    fn two_to_one(left: Self::Hash, right: Self::Hash) -> Self::Hash {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&left.to_bytes());
+        hasher.update(&right.to_bytes());
+        let digest = hasher.finalize();
+        HashOut::from_bytes(digest.as_bytes())
+    }
+}
+
+/// A `GenericConfig` using BLAKE3 (rather than Poseidon) as the circuit hash. Recursive
+/// verification of proofs under this config is not provided: `Blake3Gate`'s recursive evaluation
+/// (`eval_unfiltered_recursively`) is unimplemented.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Blake3GoldilocksConfig;
+impl GenericConfig<2> for Blake3GoldilocksConfig {
+    type F = crate::field::goldilocks_field::GoldilocksField;
+    type FE = <Self::F as RichFieldWithPoseidon>::Extension;
+    type Hasher = Blake3Hash<32>;
+    type InnerHasher = crate::hash::poseidon::PoseidonHash;
+}
@@ -0,0 +1,3 @@
+pub mod blake3;
+pub mod hash_types;
+pub mod poseidon;